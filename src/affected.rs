@@ -0,0 +1,192 @@
+use glob::Pattern;
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+use crate::{GitlabCIConfig, Job, JobName};
+
+/// Builds a [`Trie`] over the changed-file set, keyed on path components, so
+/// a job's literal directory-prefix `changes:` patterns (e.g.
+/// `src/parser/**`) can be narrowed to the handful of changed files under
+/// that prefix instead of globbing every changed file against every job.
+#[derive(Debug, Default)]
+struct TrieBuilder {
+    root: TrieNode,
+}
+
+#[derive(Debug, Default)]
+struct TrieNode {
+    children: HashMap<String, TrieNode>,
+    // Changed paths that pass through this node, i.e. share this prefix.
+    paths: Vec<PathBuf>,
+}
+
+impl TrieBuilder {
+    fn insert(&mut self, path: &Path) {
+        let mut node = &mut self.root;
+        node.paths.push(path.to_path_buf());
+        for component in path.components() {
+            let key = component.as_os_str().to_string_lossy().into_owned();
+            node = node.children.entry(key).or_default();
+            node.paths.push(path.to_path_buf());
+        }
+    }
+
+    fn build(self) -> Trie {
+        Trie { root: self.root }
+    }
+}
+
+struct Trie {
+    root: TrieNode,
+}
+
+impl Trie {
+    /// Does any changed file match this `changes:` glob pattern? Walks the
+    /// trie down the pattern's literal prefix to narrow the candidate set,
+    /// then falls back to full glob matching on whatever wildcard segments
+    /// remain (if any).
+    fn matches(&self, pattern: &str) -> bool {
+        let mut node = &self.root;
+        for component in literal_prefix(pattern) {
+            match node.children.get(component) {
+                Some(child) => node = child,
+                None => return false,
+            }
+        }
+        if node.paths.is_empty() {
+            return false;
+        }
+        match Pattern::new(pattern) {
+            Ok(glob) => node.paths.iter().any(|p| glob.matches_path(p)),
+            Err(_) => false,
+        }
+    }
+}
+
+// Path components of `pattern` up to (but not including) the first one
+// containing a glob metacharacter.
+fn literal_prefix(pattern: &str) -> impl Iterator<Item = &str> {
+    pattern
+        .split('/')
+        .take_while(|component| !component.contains(['*', '?', '[', ']']))
+}
+
+/// Returns the job names that would run for this set of changed files: jobs
+/// with no `changes:` condition are always affected (matching GitLab), the
+/// rest are affected when one of their `changes:` glob patterns matches a
+/// changed file.
+pub(crate) fn jobs_affected_by(config: &GitlabCIConfig, changed: &[&Path]) -> BTreeSet<JobName> {
+    let mut builder = TrieBuilder::default();
+    for path in changed {
+        builder.insert(path);
+    }
+    let trie = builder.build();
+
+    // Resolve each job name to its effective (most-local) definition before
+    // testing `changes:`, the same shadowing precedence `lookup_job` already
+    // gives `resolve_job` - a name redefined by an including file should only
+    // ever be judged by that definition, not also by the shadowed one.
+    let mut affected = BTreeSet::new();
+    for name in all_job_names(config) {
+        if let Some(job) = config.lookup_job(&name) {
+            if job_is_affected(&job, &trie) {
+                affected.insert(name);
+            }
+        }
+    }
+    affected
+}
+
+fn all_job_names(config: &GitlabCIConfig) -> BTreeSet<JobName> {
+    let mut names = config
+        .parent
+        .as_deref()
+        .map(all_job_names)
+        .unwrap_or_default();
+    names.extend(config.jobs.keys().cloned());
+    names
+}
+
+fn job_is_affected(job: &Job, trie: &Trie) -> bool {
+    match &job.changes {
+        None => true,
+        Some(patterns) => patterns.iter().any(|pattern| trie.matches(pattern)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GitlabCIConfig;
+    use indexmap::IndexMap;
+    use std::collections::BTreeMap;
+    use std::path::PathBuf;
+    use std::rc::Rc;
+
+    fn job_with_changes(changes: Option<Vec<&str>>) -> Job {
+        Job {
+            stage: None,
+            image: None,
+            before_script: None,
+            script: None,
+            after_script: None,
+            variables: None,
+            extends: None,
+            changes: changes.map(|c| c.into_iter().map(str::to_owned).collect()),
+            extends_jobs: vec![],
+        }
+    }
+
+    fn config(jobs: Vec<(&str, Job)>, parent: Option<GitlabCIConfig>) -> GitlabCIConfig {
+        GitlabCIConfig {
+            file: PathBuf::from("/virtual/.gitlab-ci.yml"),
+            parent: parent.map(Box::new),
+            variables: IndexMap::new(),
+            stages: vec![],
+            jobs: jobs
+                .into_iter()
+                .map(|(n, j)| (n.to_owned(), Rc::new(j)))
+                .collect::<BTreeMap<_, _>>(),
+            default: None,
+            failed_includes: vec![],
+        }
+    }
+
+    #[test]
+    fn job_without_changes_is_always_affected() {
+        let trie = TrieBuilder::default().build();
+        assert!(job_is_affected(&job_with_changes(None), &trie));
+    }
+
+    #[test]
+    fn matches_literal_prefix_and_wildcard_suffix() {
+        let mut builder = TrieBuilder::default();
+        builder.insert(&PathBuf::from("src/parser/lib.rs"));
+        builder.insert(&PathBuf::from("docs/readme.md"));
+        let trie = builder.build();
+
+        let job = job_with_changes(Some(vec!["src/parser/**/*.rs"]));
+        assert!(job_is_affected(&job, &trie));
+
+        let job = job_with_changes(Some(vec!["infra/**"]));
+        assert!(!job_is_affected(&job, &trie));
+    }
+
+    #[test]
+    fn shadowed_job_is_judged_by_its_effective_definition() {
+        // Parent's `deploy` has no changes: condition (always affected), but
+        // the child redefines it with a changes: pattern that doesn't match -
+        // the effective (child) definition should win, not a union of both.
+        let parent = config(
+            vec![("deploy", job_with_changes(None))],
+            None,
+        );
+        let child = config(
+            vec![("deploy", job_with_changes(Some(vec!["infra/**"])))],
+            Some(parent),
+        );
+
+        let affected = jobs_affected_by(&child, &[&PathBuf::from("src/lib.rs")]);
+        assert!(!affected.contains("deploy"));
+    }
+}
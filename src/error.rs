@@ -0,0 +1,61 @@
+use std::fmt;
+
+/// Broad category of a [`ParseError`], so callers can match on `class`
+/// programmatically instead of parsing `message`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorClass {
+    /// A `.gitlab-ci.yml` (or an include) couldn't be read from disk.
+    Io,
+    /// The YAML itself didn't parse.
+    Yaml,
+    /// `yaml-merge-keys` couldn't resolve a `<<:` merge key.
+    MergeKeys,
+    /// An `include:` entry couldn't be fetched by the active `IncludeResolver`.
+    Include,
+    /// An `extends:` target doesn't exist. (A missing `!reference` target is
+    /// [`ErrorClass::Reference`].)
+    JobNotFound,
+    /// A `!reference` tag pointed at a path that doesn't resolve, or forms a cycle.
+    Reference,
+    /// Two or more jobs' `extends:` form a cycle.
+    ExtendsCycle,
+}
+
+/// This crate's error type. Every fallible operation returns one of these
+/// instead of an opaque `Box<dyn Error>`, so callers can match on `class`
+/// to distinguish e.g. "file not found" from "YAML parse error" without
+/// string-matching `message`.
+#[derive(Debug)]
+pub struct ParseError {
+    pub class: ErrorClass,
+    pub message: String,
+}
+
+impl ParseError {
+    pub fn new(class: ErrorClass, message: impl Into<String>) -> Self {
+        ParseError {
+            class,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}: {}", self.class, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl From<std::io::Error> for ParseError {
+    fn from(err: std::io::Error) -> Self {
+        ParseError::new(ErrorClass::Io, err.to_string())
+    }
+}
+
+impl From<serde_yaml::Error> for ParseError {
+    fn from(err: serde_yaml::Error) -> Self {
+        ParseError::new(ErrorClass::Yaml, err.to_string())
+    }
+}
@@ -0,0 +1,105 @@
+use indexmap::IndexMap;
+
+use crate::{VarName, VarValue};
+
+/// Expands `$VAR`/`${VAR}` references in `vars`, left to right, so a later
+/// variable may reference any variable defined earlier in the same map -
+/// GitLab's imperative (not declarative) expansion order, see the note on
+/// [`crate::GitlabCIConfig`]. `$$` is an escaped, literal `$`. A reference to
+/// a variable not yet defined (or never defined) is left untouched.
+pub fn expand_variables(vars: &IndexMap<VarName, VarValue>) -> IndexMap<VarName, VarValue> {
+    let mut expanded = IndexMap::new();
+    for (key, value) in vars {
+        let value = expand_value(value, &expanded);
+        expanded.insert(key.clone(), value);
+    }
+    expanded
+}
+
+/// Expands `$VAR`/`${VAR}` references inside a single string (e.g. a
+/// `script:` line) against an already-resolved variable map.
+pub fn expand_value(value: &str, vars: &IndexMap<VarName, VarValue>) -> String {
+    let chars: Vec<char> = value.chars().collect();
+    let mut out = String::with_capacity(value.len());
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i] != '$' {
+            out.push(chars[i]);
+            i += 1;
+            continue;
+        }
+        if chars.get(i + 1) == Some(&'$') {
+            out.push('$');
+            i += 2;
+            continue;
+        }
+        if chars.get(i + 1) == Some(&'{') {
+            if let Some(len) = chars[i + 2..].iter().position(|&c| c == '}') {
+                let name: String = chars[i + 2..i + 2 + len].iter().collect();
+                match vars.get(&name) {
+                    Some(v) => out.push_str(v),
+                    None => out.push_str(&format!("${{{}}}", name)),
+                }
+                i += 2 + len + 1;
+                continue;
+            }
+        }
+        let start = i + 1;
+        let mut end = start;
+        while end < chars.len() && (chars[end].is_alphanumeric() || chars[end] == '_') {
+            end += 1;
+        }
+        if end > start {
+            let name: String = chars[start..end].iter().collect();
+            match vars.get(&name) {
+                Some(v) => out.push_str(v),
+                None => {
+                    out.push('$');
+                    out.push_str(&name);
+                }
+            }
+            i = end;
+        } else {
+            out.push('$');
+            i += 1;
+        }
+    }
+    out
+}
+
+/// Expands `$VAR`/`${VAR}` references in each line of a `script:`/`before_script:`.
+pub fn expand_script(lines: &[String], vars: &IndexMap<VarName, VarValue>) -> Vec<String> {
+    lines.iter().map(|line| expand_value(line, vars)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expands_in_definition_order() {
+        let mut vars = IndexMap::new();
+        vars.insert("A".to_owned(), "a".to_owned());
+        vars.insert("B".to_owned(), "$A-b".to_owned());
+        vars.insert("C".to_owned(), "${B}-c".to_owned());
+        let expanded = expand_variables(&vars);
+        assert_eq!(expanded["C"], "a-b-c");
+    }
+
+    #[test]
+    fn leaves_forward_and_unknown_references_untouched() {
+        let mut vars = IndexMap::new();
+        vars.insert("A".to_owned(), "$B".to_owned());
+        vars.insert("B".to_owned(), "b".to_owned());
+        let expanded = expand_variables(&vars);
+        // $B isn't defined yet when A is expanded, so it's left alone.
+        assert_eq!(expanded["A"], "$B");
+        assert_eq!(expanded["B"], "b");
+    }
+
+    #[test]
+    fn double_dollar_is_a_literal_dollar() {
+        let vars = IndexMap::new();
+        assert_eq!(expand_value("price: $$5", &vars), "price: $5");
+    }
+}
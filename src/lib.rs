@@ -1,12 +1,26 @@
+use indexmap::IndexMap;
 use serde_derive::*;
 use serde_yaml::{Mapping, Value};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use tracing::{debug, error, info, warn};
 use yaml_merge_keys::merge_keys_serde;
 
-pub type DynErr = Box<dyn std::error::Error + 'static>;
+mod affected;
+mod error;
+mod expand;
+mod reference;
+mod resolve;
+mod resolver;
+mod rules;
+mod validate;
+pub use error::{ErrorClass, ParseError};
+pub use resolve::ResolvedJob;
+pub use resolver::{IncludeResolver, IncludeSpec, LocalFsResolver};
+pub use validate::{Diagnostic, Severity};
+use resolver::include_spec_from_mapping;
+use rules::include_rules_match;
 
 pub type StageName = String;
 pub type JobName = String;
@@ -21,16 +35,35 @@ pub type Script = String;
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct Job {
     pub stage: Option<StageName>,
+    pub image: Option<String>,
     pub before_script: Option<Vec<Script>>,
     pub script: Option<Vec<Script>>,
+    pub after_script: Option<Vec<Script>>,
 
     /// Even though variables could be None,
     /// they could be defined in extends_job.variables
     /// (or globally)
-    pub variables: Option<BTreeMap<VarName, VarValue>>,
+    ///
+    /// Kept in insertion (i.e. file) order rather than a `BTreeMap` so that
+    /// [`Job::expand_variables`] can resolve `$VAR` references against only
+    /// the variables defined earlier, matching GitLab's imperative ordering.
+    pub variables: Option<IndexMap<VarName, VarValue>>,
 
     pub extends: Option<Vec<JobName>>,
 
+    /// Glob patterns from `only: changes:` and/or any `rules: - changes:`
+    /// entries, e.g. `src/**/*.rs`. Used by
+    /// [`GitlabCIConfig::jobs_affected_by`] to work out whether this job
+    /// would run for a given set of changed files. `None` means the job has
+    /// no `changes:` condition, so it's always affected (GitLab semantics).
+    ///
+    /// Patterns are pooled across every `rules:` entry regardless of that
+    /// rule's own `if:`/`when:`, so a job with one unconditional rule and one
+    /// `changes:`-gated rule looks conditionally-affected here rather than
+    /// always-affected - an approximation, not a precise match of GitLab's
+    /// per-rule semantics.
+    pub changes: Option<Vec<String>>,
+
     /// You can alas extend more than one job.
     #[serde(skip)]
     pub extends_jobs: Vec<Rc<Job>>,
@@ -38,24 +71,51 @@ pub struct Job {
 
 impl Job {
     /// Returns the consolidated local variables based on all extends.
-    pub fn get_merged_variables(&self) -> BTreeMap<String, String> {
-        let mut results = BTreeMap::new();
+    pub fn get_merged_variables(&self) -> IndexMap<VarName, VarValue> {
+        let mut results = IndexMap::new();
         self.calculate_variables(&mut results);
         results
     }
 
-    fn calculate_variables(&self, mut variables: &mut BTreeMap<String, String>) {
+    /// Like [`Job::get_merged_variables`], but with `$VAR`/`${VAR}` references
+    /// inside each value resolved against variables defined earlier in the chain.
+    pub fn expand_variables(&self) -> IndexMap<VarName, VarValue> {
+        expand::expand_variables(&self.get_merged_variables())
+    }
+
+    /// Expands `$VAR`/`${VAR}` references in this job's `script:` lines against `vars`.
+    pub fn expand_script(&self, vars: &IndexMap<VarName, VarValue>) -> Option<Vec<Script>> {
+        self.script.as_ref().map(|lines| expand::expand_script(lines, vars))
+    }
+
+    /// Expands `$VAR`/`${VAR}` references in this job's `before_script:` lines against `vars`.
+    pub fn expand_before_script(&self, vars: &IndexMap<VarName, VarValue>) -> Option<Vec<Script>> {
+        self.before_script
+            .as_ref()
+            .map(|lines| expand::expand_script(lines, vars))
+    }
+
+    fn calculate_variables(&self, mut variables: &mut IndexMap<VarName, VarValue>) {
         for parent in &self.extends_jobs {
             parent.calculate_variables(&mut variables);
         }
         if let Some(ref var) = self.variables {
             for (k, v) in var.iter() {
-                variables.insert(k.clone(), v.clone());
+                insert_in_definition_order(variables, k.clone(), v.clone());
             }
         }
     }
 }
 
+// Inserts `key`/`value`, moving `key` to the end if it already exists rather
+// than leaving it at its previous (now-stale) position - a variable
+// overridden here is *defined* here, as far as expand_variables' left-to-right
+// pass is concerned, even if a parent/earlier source set it first.
+fn insert_in_definition_order(variables: &mut IndexMap<VarName, VarValue>, key: VarName, value: VarValue) {
+    variables.shift_remove(&key);
+    variables.insert(key, value);
+}
+
 /// This is a parsed GitLabCI config.
 /// GitLab is fairly imperative rather than declarative,
 /// in that if `script:` is defined in the map before `variables:`
@@ -68,24 +128,51 @@ pub struct GitlabCIConfig {
     /// Based on include orderings, what's the parent of this gitlab config.
     pub parent: Option<Box<GitlabCIConfig>>,
 
-    /// Global variables
-    pub variables: BTreeMap<VarName, VarValue>,
+    /// Global variables. Kept in file order (rather than a `BTreeMap`) so
+    /// that [`GitlabCIConfig::expand_variables`] can resolve `$VAR`
+    /// references against only the variables already defined at that point.
+    pub variables: IndexMap<VarName, VarValue>,
 
     /// Stages group jobs that run in parallel. The ordering is important
     pub stages: Vec<StageName>,
 
     /// Targets that gitlab can run.
     pub jobs: BTreeMap<JobName, Rc<Job>>,
+
+    /// The top-level `default:` block - fallback `before_script`, `image`,
+    /// `after_script`, `variables`, etc. applied to every job that doesn't
+    /// set its own. See [`Job::resolve`].
+    pub default: Option<Job>,
+
+    /// Descriptions of `include:` entries that failed to resolve while
+    /// parsing this file specifically (not its parents). Surfaced as
+    /// [`Diagnostic`]s by [`GitlabCIConfig::validate`].
+    pub failed_includes: Vec<String>,
 }
 
 impl GitlabCIConfig {
     /// Returns the consolidated global variables based on all imports.
-    pub fn get_merged_variables(&self) -> BTreeMap<String, String> {
-        let mut results = BTreeMap::new();
+    pub fn get_merged_variables(&self) -> IndexMap<VarName, VarValue> {
+        let mut results = IndexMap::new();
         self.calculate_variables(&mut results);
         results
     }
 
+    /// Like [`GitlabCIConfig::get_merged_variables`], but with `$VAR`/`${VAR}`
+    /// references inside each value resolved against variables defined
+    /// earlier in the chain - GitLab expands variables imperatively, not
+    /// declaratively, as noted above.
+    pub fn expand_variables(&self) -> IndexMap<VarName, VarValue> {
+        expand::expand_variables(&self.get_merged_variables())
+    }
+
+    /// Returns the names of jobs that would run if exactly `changed` had
+    /// been modified, across this config's whole include/parent chain. Jobs
+    /// without a `changes:` condition are always affected, matching GitLab.
+    pub fn jobs_affected_by(&self, changed: &[&Path]) -> BTreeSet<JobName> {
+        affected::jobs_affected_by(self, changed)
+    }
+
     pub fn lookup_job(&self, job_name: &str) -> Option<Rc<Job>> {
         if let Some(job) = self.jobs.get(job_name) {
             Some(job.clone())
@@ -98,11 +185,29 @@ impl GitlabCIConfig {
         }
     }
 
-    fn calculate_variables(&self, mut variables: &mut BTreeMap<String, String>) {
+    /// Looks up `job_name` and returns its fully deep-merged [`ResolvedJob`]
+    /// (`extends` parents and the `default:` block applied), rather than its
+    /// shallow locally-declared fields.
+    pub fn resolve_job(&self, job_name: &str) -> Option<ResolvedJob> {
+        let job = self.lookup_job(job_name)?;
+        Some(job.resolve(self.effective_default()))
+    }
+
+    // This file's own `default:` block, or the closest ancestor's if this
+    // file doesn't define one.
+    fn effective_default(&self) -> Option<&Job> {
+        self.default
+            .as_ref()
+            .or_else(|| self.parent.as_deref().and_then(|p| p.effective_default()))
+    }
+
+    fn calculate_variables(&self, mut variables: &mut IndexMap<VarName, VarValue>) {
         if let Some(ref parent) = self.parent {
             parent.calculate_variables(&mut variables);
         }
-        variables.extend(self.variables.clone());
+        for (k, v) in self.variables.iter() {
+            insert_in_definition_order(variables, k.clone(), v.clone());
+        }
     }
 }
 
@@ -111,23 +216,20 @@ fn parse_includes(
     context: &Path,
     include: &Value,
     parent: Option<GitlabCIConfig>,
+    resolver: &dyn IncludeResolver,
+    failed_includes: &mut Vec<String>,
 ) -> Option<GitlabCIConfig> {
     match include {
         Value::String(include_filename) => {
-            // Remove leading '/' - join (correctly) won't concat them if filename starts from root.
-            let ch = include_filename.chars().next().unwrap();
-            let include_filename = if ch == '/' || ch == '\\' {
-                include_filename[1..].to_owned()
-            } else {
-                include_filename.to_owned()
+            let spec = IncludeSpec::Local {
+                file: include_filename.to_owned(),
             };
-            let include_filename = context.join(&include_filename);
-            parse_aux(&context.join(&Path::new(&include_filename)), parent).ok()
+            fetch_and_parse(context, &spec, parent, resolver, failed_includes)
         }
         Value::Sequence(includes) => {
             let mut parent = parent;
             for include in includes {
-                parent = parse_includes(context, include, parent);
+                parent = parse_includes(context, include, parent, resolver, failed_includes);
                 if let Some(ref parent) = parent {
                     debug!("parent returned {:?}", &parent.file);
                 } else {
@@ -140,65 +242,100 @@ fn parse_includes(
             parent
         }
         Value::Mapping(map) => {
-            if let Some(Value::String(local)) = map.get(&Value::String("local".to_owned())) {
-                let local = context.join(local);
-                parse_aux(&local, parent).ok()
-            } else if let Some(Value::String(project)) =
-                map.get(&Value::String("project".to_owned()))
-            {
-                // We assume that the included project is checked out in a sister directory.
-                let parts = project.split('/');
-                let project_name = parts.last().expect("project name should contain '/'");
-
-                if let Value::String(file) = map
-                    .get(&Value::String("file".to_owned()))
-                    .unwrap_or(&Value::String(".gitlab-ci.yml".to_owned()))
-                {
-                    let path = context.join(
-                        Path::new("..")
-                            .join(Path::new(project_name))
-                            .join(Path::new(file)),
-                    );
-                    parse_aux(&path, parent).ok()
-                } else {
-                    parent
-                }
-            } else {
-                parent
+            // rules: is evaluated against the variables visible so far in the
+            // include chain - if nothing matches, the whole entry (and its
+            // jobs/variables) never enters the config.
+            let vars_so_far = parent
+                .as_ref()
+                .map(|p| p.get_merged_variables())
+                .unwrap_or_default();
+            if !include_rules_match(context, map, &vars_so_far) {
+                debug!("include skipped, no rules: matched: {:?}", map);
+                return parent;
+            }
+            match include_spec_from_mapping(map) {
+                Some(spec) => fetch_and_parse(context, &spec, parent, resolver, failed_includes),
+                None => parent,
             }
         }
         _ => parent,
     }
 }
 
+fn fetch_and_parse(
+    context: &Path,
+    spec: &IncludeSpec,
+    parent: Option<GitlabCIConfig>,
+    resolver: &dyn IncludeResolver,
+    failed_includes: &mut Vec<String>,
+) -> Option<GitlabCIConfig> {
+    match resolver.fetch(context, spec) {
+        Ok((path, content)) => parse_from_content(&path, &content, parent, resolver).ok(),
+        Err(err) => {
+            error!("couldn't resolve include {:?}: {}", spec, err);
+            failed_includes.push(format!("{:?}: {}", spec, err));
+            parent
+        }
+    }
+}
+
 ///
 /// Taking a path to a .gitlab-ci.yml file will read it and parse it where possible.
 /// Anything unknown will be silently skipped. Jobs will be linked up with their parents.
 ///
-pub fn parse(gitlab_file: &Path) -> Result<GitlabCIConfig, DynErr> {
-    parse_aux(gitlab_file, None)
+/// `local:` and `project:` includes are resolved from the filesystem, assuming
+/// sister-checked-out projects. Use [`parse_with_resolver`] to plug in a
+/// resolver that can also follow `remote:`/`template:` includes.
+pub fn parse(gitlab_file: &Path) -> Result<GitlabCIConfig, ParseError> {
+    parse_with_resolver(gitlab_file, &LocalFsResolver)
+}
+
+/// Like [`parse`], but every `include:` entry is dispatched through the given
+/// [`IncludeResolver`] instead of assuming `local:`/`project:` filesystem layout.
+pub fn parse_with_resolver(
+    gitlab_file: &Path,
+    resolver: &dyn IncludeResolver,
+) -> Result<GitlabCIConfig, ParseError> {
+    parse_aux(gitlab_file, None, resolver)
 }
 
 //#[tracing::instrument]
-fn parse_aux(gitlab_file: &Path, parent: Option<GitlabCIConfig>) -> Result<GitlabCIConfig, DynErr> {
+fn parse_aux(
+    gitlab_file: &Path,
+    parent: Option<GitlabCIConfig>,
+    resolver: &dyn IncludeResolver,
+) -> Result<GitlabCIConfig, ParseError> {
+    let raw = std::fs::read_to_string(gitlab_file)?;
+    parse_from_content(gitlab_file, &raw, parent, resolver)
+}
+
+fn parse_from_content(
+    gitlab_file: &Path,
+    raw: &str,
+    parent: Option<GitlabCIConfig>,
+    resolver: &dyn IncludeResolver,
+) -> Result<GitlabCIConfig, ParseError> {
     debug!(
         "Parsing file {:?}, parent: {:?}",
         gitlab_file,
         parent.as_ref().map(|c| c.file.clone())
     );
-    let f = std::fs::File::open(&gitlab_file)?;
-    let raw_yaml = serde_yaml::from_reader(f)?;
+    let raw_yaml = serde_yaml::from_str(raw)?;
 
-    let val: serde_yaml::Value = merge_keys_serde(raw_yaml).expect("Couldn't merge yaml :<<");
+    let val: serde_yaml::Value = merge_keys_serde(raw_yaml)
+        .map_err(|e| ParseError::new(ErrorClass::MergeKeys, e.to_string()))?;
     let mut config = GitlabCIConfig {
         file: gitlab_file.to_path_buf(),
         parent: None,
         stages: Vec::new(),
-        variables: BTreeMap::new(),
+        variables: IndexMap::new(),
         jobs: BTreeMap::new(),
+        default: None,
+        failed_includes: Vec::new(),
     };
 
     if let serde_yaml::Value::Mapping(map) = val {
+        let map = reference::resolve_references(&map)?;
         info!("Parsing {:?} succesful.", gitlab_file);
 
         if let Some(includes) = map.get(&Value::String("include".to_owned())) {
@@ -208,6 +345,8 @@ fn parse_aux(gitlab_file: &Path, parent: Option<GitlabCIConfig>) -> Result<Gitla
                     .expect("gitlab-ci file wasn't in a dir??"),
                 includes,
                 parent,
+                resolver,
+                &mut config.failed_includes,
             )
             .map(Box::new);
         } else {
@@ -239,6 +378,9 @@ fn parse_aux(gitlab_file: &Path, parent: Option<GitlabCIConfig>) -> Result<Gitla
                                 }
                             }
                         }
+                        ("default", Value::Mapping(default_map)) => {
+                            config.default = parse_raw_job(default_map).ok();
+                        }
                         (k, _) => {
                             let job_def = parse_job(&config, k, &map);
                             if let Ok(job) = job_def {
@@ -275,10 +417,10 @@ fn parse_value_as_strings(val: &Value) -> Option<Vec<String>> {
     }
 }
 
-fn parse_value_as_map(val: &Value) -> Option<BTreeMap<VarName, VarValue>> {
+fn parse_value_as_map(val: &Value) -> Option<IndexMap<VarName, VarValue>> {
     match val {
         Value::Mapping(mapping) => {
-            let mut res : BTreeMap<String, String> = BTreeMap::new();
+            let mut res: IndexMap<String, String> = IndexMap::new();
 
             for (k, v) in mapping.iter() {
                 match (k, v) {
@@ -303,11 +445,15 @@ fn parse_value_as_map(val: &Value) -> Option<BTreeMap<VarName, VarValue>> {
     }
 }
 
-fn parse_raw_job(yml: &Mapping) -> Result<Job, DynErr> {
+fn parse_raw_job(yml: &Mapping) -> Result<Job, ParseError> {
     let stage = yml
         .get(&Value::String("stage".into()))
         .map(parse_value_as_string)
         .unwrap_or(None);
+    let image = yml
+        .get(&Value::String("image".into()))
+        .map(parse_value_as_string)
+        .unwrap_or(None);
     let before_script = yml
         .get(&Value::String("before_script".into()))
         .map(parse_value_as_strings)
@@ -316,6 +462,10 @@ fn parse_raw_job(yml: &Mapping) -> Result<Job, DynErr> {
         .get(&Value::String("script".into()))
         .map(parse_value_as_strings)
         .unwrap_or(None);
+    let after_script = yml
+        .get(&Value::String("after_script".into()))
+        .map(parse_value_as_strings)
+        .unwrap_or(None);
     let extends = yml
         .get(&Value::String("extends".into()))
         .map(parse_value_as_strings)
@@ -324,21 +474,84 @@ fn parse_raw_job(yml: &Mapping) -> Result<Job, DynErr> {
         .get(&Value::String("variables".into()))
         .map(parse_value_as_map)
         .unwrap_or(None);
+    let changes = parse_changes(yml);
 
     Ok(Job {
         stage,
+        image,
         before_script,
         script,
+        after_script,
         variables,
         extends,
+        changes,
         extends_jobs: vec![],
     })
 }
 
+// Pulls glob patterns out of `only: changes: [...]` and any `rules: - changes: [...]`
+// entries. Returns `None` if the job has no `changes:` condition at all.
+fn parse_changes(yml: &Mapping) -> Option<Vec<String>> {
+    let mut patterns = Vec::new();
+
+    if let Some(Value::Mapping(only)) = yml.get(&Value::String("only".into())) {
+        if let Some(changes) = only.get(&Value::String("changes".into())) {
+            patterns.extend(parse_value_as_strings(changes).unwrap_or_default());
+        }
+    }
+
+    if let Some(Value::Sequence(rules)) = yml.get(&Value::String("rules".into())) {
+        for rule in rules {
+            if let Value::Mapping(rule) = rule {
+                if let Some(changes) = rule.get(&Value::String("changes".into())) {
+                    patterns.extend(parse_value_as_strings(changes).unwrap_or_default());
+                }
+            }
+        }
+    }
+
+    if patterns.is_empty() {
+        None
+    } else {
+        Some(patterns)
+    }
+}
+
 // When a file is loaded, all includes are imported, then all jobs, then
 // only then do we load the jobs of the file that included us.
-#[tracing::instrument]
-fn parse_job(config: &GitlabCIConfig, job_name: &str, top: &Mapping) -> Result<Rc<Job>, DynErr> {
+fn parse_job(config: &GitlabCIConfig, job_name: &str, top: &Mapping) -> Result<Rc<Job>, ParseError> {
+    let mut visiting = BTreeSet::new();
+    parse_job_visiting(config, job_name, top, &mut visiting)
+}
+
+// Same as `parse_job`, but carries the set of job names currently being
+// parsed further up the `extends` chain, so a mutual/self `extends` cycle
+// (e.g. `a: {extends: [b]}` / `b: {extends: [a]}`) is rejected instead of
+// recursing forever and overflowing the stack.
+#[tracing::instrument(skip(visiting))]
+fn parse_job_visiting(
+    config: &GitlabCIConfig,
+    job_name: &str,
+    top: &Mapping,
+    visiting: &mut BTreeSet<JobName>,
+) -> Result<Rc<Job>, ParseError> {
+    if !visiting.insert(job_name.to_owned()) {
+        return Err(ParseError::new(
+            ErrorClass::ExtendsCycle,
+            format!("extends cycle detected at '{}'", job_name),
+        ));
+    }
+    let result = parse_job_body(config, job_name, top, visiting);
+    visiting.remove(job_name);
+    result
+}
+
+fn parse_job_body(
+    config: &GitlabCIConfig,
+    job_name: &str,
+    top: &Mapping,
+    visiting: &mut BTreeSet<JobName>,
+) -> Result<Rc<Job>, ParseError> {
     let job_nm = Value::String(job_name.to_owned());
     if let Some(Value::Mapping(job)) = top.get(&job_nm) {
         let j: Result<Job, _> = parse_raw_job(job);
@@ -349,7 +562,7 @@ fn parse_job(config: &GitlabCIConfig, job_name: &str, top: &Mapping) -> Result<R
                     let job: Option<Rc<Job>> = if job_name != parent_job_name
                         && top.contains_key(&Value::String(parent_job_name.clone()))
                     {
-                        parse_job(config, parent_job_name, top).ok()
+                        parse_job_visiting(config, parent_job_name, top, visiting).ok()
                     } else {
                         config.lookup_job(parent_job_name)
                     };
@@ -363,10 +576,10 @@ fn parse_job(config: &GitlabCIConfig, job_name: &str, top: &Mapping) -> Result<R
             Err(j.unwrap_err())
         }
     } else {
-        Err(Box::new(std::io::Error::new(
-            std::io::ErrorKind::NotFound,
-            "Job not found",
-        )))
+        Err(ParseError::new(
+            ErrorClass::JobNotFound,
+            format!("job {:?} not found", job_name),
+        ))
     }
 }
 
@@ -378,7 +591,7 @@ pub mod tests {
     use tracing_subscriber;
 
     #[test]
-    pub fn parse_example() -> Result<(), DynErr> {
+    pub fn parse_example() -> Result<(), ParseError> {
         let example_file: PathBuf = PathBuf::from(file!())
             .parent()
             .unwrap()
@@ -407,7 +620,7 @@ pub mod tests {
     }
 
     #[test]
-    pub fn parse_include() -> Result<(), DynErr> {
+    pub fn parse_include() -> Result<(), ParseError> {
         let example_file: PathBuf = PathBuf::from(file!())
             .parent()
             .unwrap()
@@ -422,7 +635,7 @@ pub mod tests {
     }
 
     #[test]
-    pub fn consolidated_global_vars() -> Result<(), DynErr> {
+    pub fn consolidated_global_vars() -> Result<(), ParseError> {
         let example_file: PathBuf = PathBuf::from(file!())
             .parent()
             .unwrap()
@@ -434,7 +647,7 @@ pub mod tests {
     }
 
     #[test]
-    pub fn imports() -> Result<(), DynErr> {
+    pub fn imports() -> Result<(), ParseError> {
         let subscriber = tracing_subscriber::fmt()
             // all spans/events with a level higher than TRACE (e.g, debug, info, warn, etc.)
             // will be written to stdout.
@@ -465,4 +678,43 @@ pub mod tests {
         });
         Ok(())
     }
+
+    #[test]
+    pub fn mutual_extends_does_not_overflow_the_stack() -> Result<(), ParseError> {
+        let yaml = r#"
+stages: ["test"]
+a:
+  stage: test
+  extends: [b]
+  script: ["echo a"]
+b:
+  stage: test
+  extends: [a]
+  script: ["echo b"]
+"#;
+        let config = parse_from_content(Path::new("mutual.yml"), yaml, None, &LocalFsResolver)?;
+        assert!(config.jobs.contains_key("a"));
+        assert!(config.jobs.contains_key("b"));
+        Ok(())
+    }
+
+    #[test]
+    pub fn overriding_a_variable_moves_it_to_its_effective_definition_point() -> Result<(), ParseError> {
+        let yaml = r#"
+base:
+  variables:
+    A: "1"
+child:
+  extends: [base]
+  variables:
+    NEW: "hello"
+    A: "$NEW-2"
+"#;
+        let config = parse_from_content(Path::new("override.yml"), yaml, None, &LocalFsResolver)?;
+        let child = config.jobs.get("child").unwrap();
+        // NEW is textually defined before A is re-assigned here, so A's
+        // override should be able to see it, even though a parent set A first.
+        assert_eq!(child.expand_variables()["A"], "hello-2");
+        Ok(())
+    }
 }
@@ -0,0 +1,196 @@
+use serde_yaml::{Mapping, Value};
+use std::collections::BTreeSet;
+
+use crate::{ErrorClass, ParseError};
+
+const REFERENCE_TAG: &str = "!reference";
+
+/// Splices every `!reference [job, key, ...]` tag found anywhere under
+/// `top` with the value it points to, resolving nested references too, and
+/// returning an error on a reference cycle rather than recursing forever.
+pub(crate) fn resolve_references(top: &Mapping) -> Result<Mapping, ParseError> {
+    let mut visiting = BTreeSet::new();
+    match resolve_value(&Value::Mapping(top.clone()), top, &mut visiting)? {
+        Value::Mapping(resolved) => Ok(resolved),
+        _ => unreachable!("resolving a Value::Mapping always yields a Value::Mapping"),
+    }
+}
+
+fn resolve_value(
+    value: &Value,
+    top: &Mapping,
+    visiting: &mut BTreeSet<Vec<String>>,
+) -> Result<Value, ParseError> {
+    match value {
+        Value::Tagged(tagged) if tagged.tag.to_string() == REFERENCE_TAG => {
+            let path = match &tagged.value {
+                Value::Sequence(seq) => seq.clone(),
+                other => vec![other.clone()],
+            };
+            let path_key: Vec<String> = path.iter().map(describe).collect();
+
+            if !visiting.insert(path_key.clone()) {
+                return Err(ParseError::new(
+                    ErrorClass::Reference,
+                    format!("!reference cycle detected at [{}]", path_key.join(", ")),
+                ));
+            }
+
+            let target = lookup_path(top, &path).ok_or_else(|| {
+                ParseError::new(
+                    ErrorClass::Reference,
+                    format!("!reference [{}] didn't resolve to anything", path_key.join(", ")),
+                )
+            })?;
+            let resolved = resolve_value(target, top, visiting)?;
+            visiting.remove(&path_key);
+            Ok(resolved)
+        }
+        Value::Sequence(seq) => {
+            let mut resolved = Vec::with_capacity(seq.len());
+            for v in seq {
+                // A `!reference` element that itself points at a sequence (e.g.
+                // `script: [!reference [.setup, script], "echo build"]`) is
+                // spliced into the parent list in place, same as GitLab does,
+                // rather than nested as a list-within-a-list.
+                match (is_reference_tag(v), resolve_value(v, top, visiting)?) {
+                    (true, Value::Sequence(spliced)) => resolved.extend(spliced),
+                    (_, other) => resolved.push(other),
+                }
+            }
+            Ok(Value::Sequence(resolved))
+        }
+        Value::Mapping(map) => {
+            let mut resolved = Mapping::new();
+            for (k, v) in map.iter() {
+                resolved.insert(k.clone(), resolve_value(v, top, visiting)?);
+            }
+            Ok(Value::Mapping(resolved))
+        }
+        other => Ok(other.clone()),
+    }
+}
+
+fn is_reference_tag(value: &Value) -> bool {
+    matches!(value, Value::Tagged(tagged) if tagged.tag.to_string() == REFERENCE_TAG)
+}
+
+fn describe(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Number(n) => n.to_string(),
+        other => format!("{:?}", other),
+    }
+}
+
+// Walks `path` (e.g. `[job_name, "script"]`) from the root of `top`, indexing
+// into mappings by key and sequences by integer index.
+fn lookup_path<'a>(top: &'a Mapping, path: &[Value]) -> Option<&'a Value> {
+    let (first, rest) = path.split_first()?;
+    let mut current = top.get(first)?;
+    for key in rest {
+        current = match current {
+            Value::Mapping(m) => m.get(key)?,
+            Value::Sequence(s) => s.get(key.as_u64()? as usize)?,
+            _ => return None,
+        };
+    }
+    Some(current)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(yaml: &str) -> Mapping {
+        match serde_yaml::from_str(yaml).unwrap() {
+            Value::Mapping(m) => m,
+            _ => panic!("expected a mapping"),
+        }
+    }
+
+    #[test]
+    fn bare_reference_resolves_to_target_value() {
+        let top = parse(
+            r#"
+.setup:
+  script: ["echo setup"]
+build:
+  script: !reference [.setup, script]
+"#,
+        );
+        let resolved = resolve_references(&top).unwrap();
+        let build = resolved.get("build").unwrap();
+        assert_eq!(
+            build.get("script").unwrap(),
+            &Value::Sequence(vec![Value::String("echo setup".to_owned())])
+        );
+    }
+
+    #[test]
+    fn reference_in_list_splices_in_place_rather_than_nesting() {
+        let top = parse(
+            r#"
+.setup:
+  script: ["echo setup"]
+build:
+  script: [!reference [.setup, script], "echo build"]
+"#,
+        );
+        let resolved = resolve_references(&top).unwrap();
+        let build = resolved.get("build").unwrap();
+        assert_eq!(
+            build.get("script").unwrap(),
+            &Value::Sequence(vec![
+                Value::String("echo setup".to_owned()),
+                Value::String("echo build".to_owned()),
+            ])
+        );
+    }
+
+    #[test]
+    fn nested_references_resolve_transitively() {
+        let top = parse(
+            r#"
+.base:
+  script: ["echo base"]
+.setup:
+  script: !reference [.base, script]
+build:
+  script: !reference [.setup, script]
+"#,
+        );
+        let resolved = resolve_references(&top).unwrap();
+        let build = resolved.get("build").unwrap();
+        assert_eq!(
+            build.get("script").unwrap(),
+            &Value::Sequence(vec![Value::String("echo base".to_owned())])
+        );
+    }
+
+    #[test]
+    fn cyclic_reference_is_an_error_not_a_stack_overflow() {
+        let top = parse(
+            r#"
+a:
+  script: !reference [b, script]
+b:
+  script: !reference [a, script]
+"#,
+        );
+        let err = resolve_references(&top).unwrap_err();
+        assert_eq!(err.class, ErrorClass::Reference);
+    }
+
+    #[test]
+    fn missing_reference_target_is_an_error() {
+        let top = parse(
+            r#"
+build:
+  script: !reference [.nope, script]
+"#,
+        );
+        let err = resolve_references(&top).unwrap_err();
+        assert_eq!(err.class, ErrorClass::Reference);
+    }
+}
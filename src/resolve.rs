@@ -0,0 +1,120 @@
+use indexmap::IndexMap;
+
+use crate::{Job, Script, StageName, VarName, VarValue};
+
+/// GitLab's actual effective definition of a job: its `extends` parents
+/// deep-merged in (last one wins for scalars and arrays, same as GitLab),
+/// with anything still unset filled in from the config's `default:` block.
+/// This is what GitLab would actually run, as opposed to [`Job`]'s shallow
+/// locally-declared fields.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ResolvedJob {
+    pub stage: Option<StageName>,
+    pub image: Option<String>,
+    pub before_script: Option<Vec<Script>>,
+    pub script: Option<Vec<Script>>,
+    pub after_script: Option<Vec<Script>>,
+    pub variables: IndexMap<VarName, VarValue>,
+}
+
+impl Job {
+    /// Deep-merges this job with its `extends` parents and `default`,
+    /// producing the effective job GitLab would run. `default` is normally
+    /// [`crate::GitlabCIConfig`]'s own `default:` block (or its closest
+    /// ancestor's, if this file doesn't define one).
+    pub fn resolve(&self, default: Option<&Job>) -> ResolvedJob {
+        let mut resolved = ResolvedJob::default();
+        if let Some(default) = default {
+            merge_job_into(&mut resolved, default);
+        }
+        merge_extends_chain(&mut resolved, self);
+        resolved
+    }
+}
+
+// Applies `job`'s extends parents (oldest first) then `job` itself, so a
+// child's fields always win over its parents', matching GitLab's last-wins
+// `extends` semantics.
+fn merge_extends_chain(resolved: &mut ResolvedJob, job: &Job) {
+    for parent in &job.extends_jobs {
+        merge_extends_chain(resolved, parent);
+    }
+    merge_job_into(resolved, job);
+}
+
+// Scalars and arrays are replaced wholesale when present (GitLab's real
+// `extends`/`default` merge doesn't concatenate arrays, only hashes); only
+// `variables:` is merged key-by-key, same as `Job::get_merged_variables`.
+fn merge_job_into(resolved: &mut ResolvedJob, job: &Job) {
+    if job.stage.is_some() {
+        resolved.stage = job.stage.clone();
+    }
+    if job.image.is_some() {
+        resolved.image = job.image.clone();
+    }
+    if job.before_script.is_some() {
+        resolved.before_script = job.before_script.clone();
+    }
+    if job.script.is_some() {
+        resolved.script = job.script.clone();
+    }
+    if job.after_script.is_some() {
+        resolved.after_script = job.after_script.clone();
+    }
+    if let Some(vars) = &job.variables {
+        for (k, v) in vars {
+            crate::insert_in_definition_order(&mut resolved.variables, k.clone(), v.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    fn job(stage: Option<&str>, script: Option<Vec<&str>>) -> Job {
+        Job {
+            stage: stage.map(str::to_owned),
+            image: None,
+            before_script: None,
+            script: script.map(|s| s.into_iter().map(str::to_owned).collect()),
+            after_script: None,
+            variables: None,
+            extends: None,
+            changes: None,
+            extends_jobs: vec![],
+        }
+    }
+
+    #[test]
+    fn default_fills_in_unset_fields() {
+        let default = job(Some("test"), Some(vec!["echo default"]));
+        let build = job(None, None);
+        let resolved = build.resolve(Some(&default));
+        assert_eq!(resolved.stage.as_deref(), Some("test"));
+        assert_eq!(resolved.script, Some(vec!["echo default".to_owned()]));
+    }
+
+    #[test]
+    fn own_fields_win_over_default() {
+        let default = job(Some("test"), Some(vec!["echo default"]));
+        let build = job(Some("deploy"), Some(vec!["echo build"]));
+        let resolved = build.resolve(Some(&default));
+        assert_eq!(resolved.stage.as_deref(), Some("deploy"));
+        assert_eq!(resolved.script, Some(vec!["echo build".to_owned()]));
+    }
+
+    #[test]
+    fn child_fields_win_over_extends_parent() {
+        let mut parent = job(Some("test"), Some(vec!["echo parent"]));
+        parent.variables = Some(IndexMap::from([("A".to_owned(), "parent".to_owned())]));
+        let mut child = job(None, Some(vec!["echo child"]));
+        child.extends_jobs = vec![Rc::new(parent)];
+
+        let resolved = child.resolve(None);
+        assert_eq!(resolved.stage.as_deref(), Some("test"));
+        assert_eq!(resolved.script, Some(vec!["echo child".to_owned()]));
+        assert_eq!(resolved.variables["A"], "parent");
+    }
+}
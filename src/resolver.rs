@@ -0,0 +1,264 @@
+use crate::{ErrorClass, ParseError};
+use std::path::{Path, PathBuf};
+
+use serde_yaml::{Mapping, Value};
+
+/// Identifies where a single `include:` entry points, independent of how
+/// it ends up being fetched.
+#[derive(Debug, Clone, PartialEq)]
+pub enum IncludeSpec {
+    /// `include: local: '/templates/foo.yml'` (or a bare string entry).
+    Local { file: String },
+
+    /// `include: project: 'group/project'` with an optional `file:` and `ref:`.
+    Project {
+        project: String,
+        file: String,
+        ref_: Option<String>,
+    },
+
+    /// `include: remote: 'https://example.com/foo.yml'`.
+    Remote { url: String },
+
+    /// `include: template: 'Foo.gitlab-ci.yml'`.
+    Template { name: String },
+}
+
+/// Resolves an [`IncludeSpec`] to the file's path and its raw YAML text.
+///
+/// This is the hook point for fetching `project:`, `remote:` and `template:`
+/// includes, which need a checkout, an HTTP call or a call to the GitLab
+/// templates API respectively. [`LocalFsResolver`] is the crate's original,
+/// filesystem-only behaviour and remains the default so existing callers of
+/// [`crate::parse`] don't need to change.
+pub trait IncludeResolver {
+    fn fetch(&self, context: &Path, spec: &IncludeSpec) -> Result<(PathBuf, String), ParseError>;
+}
+
+/// The resolver [`crate::parse`] uses by default: `local:` includes are read
+/// relative to the including file, and `project:` includes are assumed to be
+/// checked out in a sister directory (`../<project_name>/...`). It has no way
+/// to reach `remote:` or `template:` includes, since those require a network
+/// call this crate doesn't make on its own.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LocalFsResolver;
+
+impl IncludeResolver for LocalFsResolver {
+    fn fetch(&self, context: &Path, spec: &IncludeSpec) -> Result<(PathBuf, String), ParseError> {
+        match spec {
+            IncludeSpec::Local { file } => {
+                let path = join_stripping_root(context, file);
+                let content = std::fs::read_to_string(&path)?;
+                Ok((path, content))
+            }
+            IncludeSpec::Project { project, file, .. } => {
+                // We assume that the included project is checked out in a sister directory.
+                let project_name = project
+                    .split('/')
+                    .last()
+                    .expect("project name should contain '/'");
+                let path = context.join(Path::new("..").join(project_name).join(file));
+                let content = std::fs::read_to_string(&path)?;
+                Ok((path, content))
+            }
+            IncludeSpec::Remote { url } => Err(ParseError::new(
+                ErrorClass::Include,
+                format!("LocalFsResolver can't fetch remote include {:?}; pass a resolver that can", url),
+            )),
+            IncludeSpec::Template { name } => Err(ParseError::new(
+                ErrorClass::Include,
+                format!("LocalFsResolver can't fetch template include {:?}; pass a resolver that can", name),
+            )),
+        }
+    }
+}
+
+// Remove a leading '/' - join (correctly) won't concat them if filename starts from root.
+fn join_stripping_root(context: &Path, file: &str) -> PathBuf {
+    let ch = file.chars().next().unwrap();
+    let file = if ch == '/' || ch == '\\' {
+        &file[1..]
+    } else {
+        file
+    };
+    context.join(file)
+}
+
+/// Pulls an [`IncludeSpec`] out of a single mapping-form `include:` entry,
+/// e.g. `{project: 'group/project', file: 'ci/build.yml', ref: main}`.
+/// Returns `None` for mappings that don't match any known include shape
+/// (unknown keys are silently skipped, same as everywhere else in this parser).
+pub(crate) fn include_spec_from_mapping(map: &Mapping) -> Option<IncludeSpec> {
+    let get_str = |key: &str| -> Option<String> {
+        match map.get(&Value::String(key.to_owned())) {
+            Some(Value::String(s)) => Some(s.clone()),
+            _ => None,
+        }
+    };
+
+    if let Some(file) = get_str("local") {
+        Some(IncludeSpec::Local { file })
+    } else if let Some(project) = get_str("project") {
+        let file = get_str("file").unwrap_or_else(|| ".gitlab-ci.yml".to_owned());
+        let ref_ = get_str("ref");
+        Some(IncludeSpec::Project { project, file, ref_ })
+    } else if let Some(url) = get_str("remote") {
+        Some(IncludeSpec::Remote { url })
+    } else if let Some(name) = get_str("template") {
+        Some(IncludeSpec::Template { name })
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn mapping(entries: &[(&str, &str)]) -> Mapping {
+        let mut map = Mapping::new();
+        for (k, v) in entries {
+            map.insert(Value::String((*k).to_owned()), Value::String((*v).to_owned()));
+        }
+        map
+    }
+
+    #[test]
+    fn mapping_to_spec_local() {
+        let map = mapping(&[("local", "ci/build.yml")]);
+        assert_eq!(
+            include_spec_from_mapping(&map),
+            Some(IncludeSpec::Local {
+                file: "ci/build.yml".to_owned()
+            })
+        );
+    }
+
+    #[test]
+    fn mapping_to_spec_project_defaults_file_and_ref() {
+        let map = mapping(&[("project", "group/project")]);
+        assert_eq!(
+            include_spec_from_mapping(&map),
+            Some(IncludeSpec::Project {
+                project: "group/project".to_owned(),
+                file: ".gitlab-ci.yml".to_owned(),
+                ref_: None,
+            })
+        );
+    }
+
+    #[test]
+    fn mapping_to_spec_project_with_file_and_ref() {
+        let map = mapping(&[("project", "group/project"), ("file", "ci/build.yml"), ("ref", "main")]);
+        assert_eq!(
+            include_spec_from_mapping(&map),
+            Some(IncludeSpec::Project {
+                project: "group/project".to_owned(),
+                file: "ci/build.yml".to_owned(),
+                ref_: Some("main".to_owned()),
+            })
+        );
+    }
+
+    #[test]
+    fn mapping_to_spec_remote() {
+        let map = mapping(&[("remote", "https://example.com/foo.yml")]);
+        assert_eq!(
+            include_spec_from_mapping(&map),
+            Some(IncludeSpec::Remote {
+                url: "https://example.com/foo.yml".to_owned()
+            })
+        );
+    }
+
+    #[test]
+    fn mapping_to_spec_template() {
+        let map = mapping(&[("template", "Foo.gitlab-ci.yml")]);
+        assert_eq!(
+            include_spec_from_mapping(&map),
+            Some(IncludeSpec::Template {
+                name: "Foo.gitlab-ci.yml".to_owned()
+            })
+        );
+    }
+
+    #[test]
+    fn mapping_to_spec_unknown_shape_is_none() {
+        let map = mapping(&[("unsupported", "whatever")]);
+        assert_eq!(include_spec_from_mapping(&map), None);
+    }
+
+    // Each test gets its own scratch dir under the system temp dir so they
+    // don't trip over each other's fixture files when run in parallel.
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("gitlab-ci-parser-resolver-test-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn local_fs_resolver_fetches_local_include() {
+        let context = scratch_dir("local");
+        fs::write(context.join("included.yml"), "included: true").unwrap();
+
+        let (path, content) = LocalFsResolver
+            .fetch(&context, &IncludeSpec::Local { file: "included.yml".to_owned() })
+            .unwrap();
+        assert_eq!(path, context.join("included.yml"));
+        assert_eq!(content, "included: true");
+    }
+
+    #[test]
+    fn local_fs_resolver_strips_leading_slash_on_local_include() {
+        let context = scratch_dir("local-abs");
+        fs::write(context.join("included.yml"), "included: true").unwrap();
+
+        let (_, content) = LocalFsResolver
+            .fetch(&context, &IncludeSpec::Local { file: "/included.yml".to_owned() })
+            .unwrap();
+        assert_eq!(content, "included: true");
+    }
+
+    #[test]
+    fn local_fs_resolver_fetches_project_include_from_sister_dir() {
+        let root = scratch_dir("project");
+        let context = root.join("consumer");
+        let sister = root.join("other-project");
+        fs::create_dir_all(&context).unwrap();
+        fs::create_dir_all(&sister).unwrap();
+        fs::write(sister.join("ci.yml"), "project: true").unwrap();
+
+        let (path, content) = LocalFsResolver
+            .fetch(
+                &context,
+                &IncludeSpec::Project {
+                    project: "group/other-project".to_owned(),
+                    file: "ci.yml".to_owned(),
+                    ref_: None,
+                },
+            )
+            .unwrap();
+        // The path isn't normalised: it's reached via `../<project_name>/`.
+        assert_eq!(path, context.join("..").join("other-project").join("ci.yml"));
+        assert_eq!(content, "project: true");
+    }
+
+    #[test]
+    fn local_fs_resolver_cannot_fetch_remote_includes() {
+        let context = scratch_dir("remote");
+        let err = LocalFsResolver
+            .fetch(&context, &IncludeSpec::Remote { url: "https://example.com/foo.yml".to_owned() })
+            .unwrap_err();
+        assert_eq!(err.class, ErrorClass::Include);
+    }
+
+    #[test]
+    fn local_fs_resolver_cannot_fetch_template_includes() {
+        let context = scratch_dir("template");
+        let err = LocalFsResolver
+            .fetch(&context, &IncludeSpec::Template { name: "Foo.gitlab-ci.yml".to_owned() })
+            .unwrap_err();
+        assert_eq!(err.class, ErrorClass::Include);
+    }
+}
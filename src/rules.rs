@@ -0,0 +1,184 @@
+use indexmap::IndexMap;
+use regex::Regex;
+use serde_yaml::{Mapping, Value};
+use std::path::Path;
+
+/// Returns `true` if this mapping-form `include:` entry should be followed,
+/// i.e. it carries no `rules:` at all, or at least one of its `rules:`
+/// entries matches `vars` (the variables visible so far in the include chain).
+pub(crate) fn include_rules_match(
+    context: &Path,
+    map: &Mapping,
+    vars: &IndexMap<String, String>,
+) -> bool {
+    let rules = match map.get(&Value::String("rules".to_owned())) {
+        Some(Value::Sequence(rules)) => rules,
+        _ => return true,
+    };
+
+    rules.iter().any(|rule| rule_matches(context, rule, vars))
+}
+
+fn rule_matches(context: &Path, rule: &Value, vars: &IndexMap<String, String>) -> bool {
+    let rule = match rule {
+        Value::Mapping(m) => m,
+        _ => return false,
+    };
+
+    let if_matches = match rule.get(&Value::String("if".to_owned())) {
+        Some(Value::String(expr)) => eval_if(expr, vars),
+        _ => true,
+    };
+
+    let exists_matches = match rule.get(&Value::String("exists".to_owned())) {
+        Some(value) => as_string_list(value)
+            .iter()
+            .any(|pattern| glob_exists(context, pattern)),
+        None => true,
+    };
+
+    if_matches && exists_matches
+}
+
+fn as_string_list(val: &Value) -> Vec<String> {
+    match val {
+        Value::String(s) => vec![s.clone()],
+        Value::Sequence(seq) => seq
+            .iter()
+            .filter_map(|v| match v {
+                Value::String(s) => Some(s.clone()),
+                _ => None,
+            })
+            .collect(),
+        _ => vec![],
+    }
+}
+
+fn glob_exists(context: &Path, pattern: &str) -> bool {
+    let full = context.join(pattern);
+    match full.to_str() {
+        Some(pattern_str) => glob::glob(pattern_str)
+            .map(|mut paths| paths.next().is_some())
+            .unwrap_or(false),
+        None => full.exists(),
+    }
+}
+
+/// Evaluates an `if:` expression such as `$CI_COMMIT_BRANCH == "main" && $FOO =~ /^bar/`
+/// against the variables visible at this point in the include chain.
+pub(crate) fn eval_if(expr: &str, vars: &IndexMap<String, String>) -> bool {
+    split_top_level(expr, "||")
+        .iter()
+        .any(|or_term| split_top_level(or_term, "&&").iter().all(|and_term| eval_term(and_term.trim(), vars)))
+}
+
+fn eval_term(term: &str, vars: &IndexMap<String, String>) -> bool {
+    if let Some(idx) = find_top_level(term, "=~") {
+        let var = resolve_var(term[..idx].trim(), vars);
+        let rhs = term[idx + 2..].trim();
+        let pattern = rhs
+            .strip_prefix('/')
+            .and_then(|p| p.strip_suffix('/'))
+            .unwrap_or(rhs);
+        return Regex::new(pattern)
+            .map(|re| re.is_match(&var))
+            .unwrap_or(false);
+    }
+    if let Some(idx) = find_top_level(term, "!=") {
+        let var = resolve_var(term[..idx].trim(), vars);
+        return var != parse_literal(term[idx + 2..].trim());
+    }
+    if let Some(idx) = find_top_level(term, "==") {
+        let var = resolve_var(term[..idx].trim(), vars);
+        return var == parse_literal(term[idx + 2..].trim());
+    }
+    // A bare `$VAR` is truthy when it's set to a non-empty value.
+    !resolve_var(term, vars).is_empty()
+}
+
+fn resolve_var(token: &str, vars: &IndexMap<String, String>) -> String {
+    vars.get(token.trim_start_matches('$')).cloned().unwrap_or_default()
+}
+
+fn parse_literal(s: &str) -> String {
+    if s.len() >= 2
+        && ((s.starts_with('"') && s.ends_with('"')) || (s.starts_with('\'') && s.ends_with('\'')))
+    {
+        s[1..s.len() - 1].to_owned()
+    } else {
+        s.to_owned()
+    }
+}
+
+// Splits on `op` wherever it appears outside of a quoted literal.
+fn split_top_level<'a>(s: &'a str, op: &str) -> Vec<&'a str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    while let Some(idx) = find_top_level(&s[start..], op) {
+        parts.push(&s[start..start + idx]);
+        start += idx + op.len();
+    }
+    parts.push(&s[start..]);
+    parts
+}
+
+// Byte offset of the first occurrence of `op` in `s` that's outside of a
+// quoted literal, so an operator detected in e.g. `$VAR == "a!=b"` isn't
+// confused by the `!=` sitting inside the quoted right-hand side.
+fn find_top_level(s: &str, op: &str) -> Option<usize> {
+    let mut in_quotes = None;
+    let mut i = 0;
+    while i < s.len() {
+        let rest = &s[i..];
+        let c = rest.chars().next().unwrap();
+        match in_quotes {
+            Some(q) if c == q => in_quotes = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => in_quotes = Some(c),
+            None if rest.starts_with(op) => return Some(i),
+            None => {}
+        }
+        i += c.len_utf8();
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn eval_equality() {
+        let mut vars = IndexMap::new();
+        vars.insert("CI_COMMIT_BRANCH".to_owned(), "main".to_owned());
+        assert!(eval_if(r#"$CI_COMMIT_BRANCH == "main""#, &vars));
+        assert!(!eval_if(r#"$CI_COMMIT_BRANCH != "main""#, &vars));
+    }
+
+    #[test]
+    fn eval_regex_and_or() {
+        let mut vars = IndexMap::new();
+        vars.insert("CI_COMMIT_BRANCH".to_owned(), "release/1.2".to_owned());
+        assert!(eval_if(r#"$CI_COMMIT_BRANCH =~ /^release\// && $CI_COMMIT_BRANCH != "main""#, &vars));
+        assert!(eval_if(r#"$MISSING == "x" || $CI_COMMIT_BRANCH =~ /^release\//"#, &vars));
+        assert!(!eval_if(r#"$MISSING == "x" || $MISSING2 == "y""#, &vars));
+    }
+
+    #[test]
+    fn operator_inside_quoted_literal_does_not_confuse_equality() {
+        let mut vars = IndexMap::new();
+        vars.insert("VAR".to_owned(), "a!=b".to_owned());
+        assert!(eval_if(r#"$VAR == "a!=b""#, &vars));
+
+        vars.insert("VAR".to_owned(), "something else".to_owned());
+        assert!(!eval_if(r#"$VAR == "a!=b""#, &vars));
+    }
+
+    #[test]
+    fn eval_bare_var_truthiness() {
+        let mut vars = IndexMap::new();
+        vars.insert("FEATURE_FLAG".to_owned(), "1".to_owned());
+        assert!(eval_if("$FEATURE_FLAG", &vars));
+        assert!(!eval_if("$UNSET_FLAG", &vars));
+    }
+}
@@ -0,0 +1,236 @@
+use std::collections::{BTreeMap, HashSet};
+use std::rc::Rc;
+
+use crate::{GitlabCIConfig, Job, JobName, StageName};
+
+/// How serious a [`Diagnostic`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+/// A non-fatal problem found by [`GitlabCIConfig::validate`] - the config
+/// still parsed, but GitLab itself would flag this.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub job: Option<JobName>,
+    pub message: String,
+}
+
+impl GitlabCIConfig {
+    /// Walks the parsed config for problems GitLab itself would flag:
+    /// `extends:` targets that resolve to nothing, cycles in the
+    /// `extends_jobs` graph, jobs whose `stage:` isn't listed in `stages:`,
+    /// and `include:` entries that failed to resolve. Nothing here is
+    /// fatal - useful as a pre-push linter on top of a best-effort parse.
+    pub fn validate(&self) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        let stages = self.all_stages();
+
+        for (name, job) in self.all_jobs() {
+            check_extends_targets(self, &name, &job, &mut diagnostics);
+            check_extends_cycle(self, &name, &job, &mut diagnostics);
+            check_stage(&name, &job, &stages, &mut diagnostics);
+        }
+
+        for include in self.all_failed_includes() {
+            diagnostics.push(Diagnostic {
+                severity: Severity::Error,
+                job: None,
+                message: format!("include didn't resolve: {}", include),
+            });
+        }
+
+        diagnostics
+    }
+
+    // Jobs across the whole parent/include chain, deduped by name - a job
+    // redefined by an including file shadows the included file's definition
+    // entirely, the same precedence `lookup_job` already gives `resolve_job`,
+    // so only the effective definition is ever validated.
+    fn all_jobs(&self) -> Vec<(JobName, Rc<Job>)> {
+        self.all_jobs_map().into_iter().collect()
+    }
+
+    fn all_jobs_map(&self) -> BTreeMap<JobName, Rc<Job>> {
+        let mut jobs = self
+            .parent
+            .as_deref()
+            .map(|p| p.all_jobs_map())
+            .unwrap_or_default();
+        jobs.extend(self.jobs.iter().map(|(n, j)| (n.clone(), j.clone())));
+        jobs
+    }
+
+    fn all_stages(&self) -> Vec<StageName> {
+        let mut stages = self.parent.as_ref().map(|p| p.all_stages()).unwrap_or_default();
+        stages.extend(self.stages.iter().cloned());
+        stages
+    }
+
+    fn all_failed_includes(&self) -> Vec<String> {
+        let mut failed = self
+            .parent
+            .as_ref()
+            .map(|p| p.all_failed_includes())
+            .unwrap_or_default();
+        failed.extend(self.failed_includes.iter().cloned());
+        failed
+    }
+}
+
+fn check_extends_targets(config: &GitlabCIConfig, name: &str, job: &Job, out: &mut Vec<Diagnostic>) {
+    if let Some(targets) = &job.extends {
+        for target in targets {
+            if config.lookup_job(target).is_none() {
+                out.push(Diagnostic {
+                    severity: Severity::Warning,
+                    job: Some(name.to_owned()),
+                    message: format!("extends target '{}' doesn't exist", target),
+                });
+            }
+        }
+    }
+}
+
+// Traverses `extends:` by *name* rather than following `Job::extends_jobs`
+// `Rc`s: the parser itself refuses to link a cyclic `extends_jobs` edge (see
+// `parse_job_visiting` in lib.rs), so that graph can never contain a cycle by
+// construction - the raw `extends:` names are what still carry the evidence
+// of a config author's mistake.
+fn check_extends_cycle(config: &GitlabCIConfig, name: &str, job: &Job, out: &mut Vec<Diagnostic>) {
+    let mut path = HashSet::new();
+    path.insert(name.to_owned());
+    if has_cycle(config, job, &mut path) {
+        out.push(Diagnostic {
+            severity: Severity::Error,
+            job: Some(name.to_owned()),
+            message: "extends cycle detected".to_owned(),
+        });
+    }
+}
+
+fn has_cycle(config: &GitlabCIConfig, job: &Job, path: &mut HashSet<JobName>) -> bool {
+    let Some(targets) = &job.extends else {
+        return false;
+    };
+    for target in targets {
+        if !path.insert(target.clone()) {
+            return true;
+        }
+        let cyclic = config
+            .lookup_job(target)
+            .map(|parent| has_cycle(config, &parent, path))
+            .unwrap_or(false);
+        path.remove(target);
+        if cyclic {
+            return true;
+        }
+    }
+    false
+}
+
+fn check_stage(name: &str, job: &Job, stages: &[StageName], out: &mut Vec<Diagnostic>) {
+    if let Some(stage) = &job.stage {
+        if !stages.iter().any(|s| s == stage) {
+            out.push(Diagnostic {
+                severity: Severity::Warning,
+                job: Some(name.to_owned()),
+                message: format!("stage '{}' isn't listed in stages:", stage),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indexmap::IndexMap;
+    use std::collections::BTreeMap;
+    use std::path::PathBuf;
+
+    fn job(stage: Option<&str>, extends: Option<Vec<&str>>) -> Rc<Job> {
+        Rc::new(Job {
+            stage: stage.map(str::to_owned),
+            image: None,
+            before_script: None,
+            script: None,
+            after_script: None,
+            variables: None::<IndexMap<String, String>>,
+            extends: extends.map(|e| e.into_iter().map(str::to_owned).collect()),
+            changes: None,
+            extends_jobs: vec![],
+        })
+    }
+
+    fn config(jobs: Vec<(&str, Rc<Job>)>, stages: Vec<&str>) -> GitlabCIConfig {
+        config_with_parent(jobs, stages, None)
+    }
+
+    fn config_with_parent(
+        jobs: Vec<(&str, Rc<Job>)>,
+        stages: Vec<&str>,
+        parent: Option<GitlabCIConfig>,
+    ) -> GitlabCIConfig {
+        GitlabCIConfig {
+            file: PathBuf::from("/virtual/.gitlab-ci.yml"),
+            parent: parent.map(Box::new),
+            variables: IndexMap::new(),
+            stages: stages.into_iter().map(str::to_owned).collect(),
+            jobs: jobs.into_iter().map(|(n, j)| (n.to_owned(), j)).collect::<BTreeMap<_, _>>(),
+            default: None,
+            failed_includes: vec![],
+        }
+    }
+
+    #[test]
+    fn flags_dangling_extends_and_unknown_stage() {
+        let cfg = config(
+            vec![("build", job(Some("compile"), Some(vec!["missing_job"])))],
+            vec!["test"],
+        );
+        let diagnostics = cfg.validate();
+        assert!(diagnostics
+            .iter()
+            .any(|d| d.message.contains("extends target 'missing_job'")));
+        assert!(diagnostics
+            .iter()
+            .any(|d| d.message.contains("stage 'compile'")));
+    }
+
+    #[test]
+    fn flags_mutual_extends_cycle() {
+        let cfg = config(
+            vec![
+                ("a", job(Some("test"), Some(vec!["b"]))),
+                ("b", job(Some("test"), Some(vec!["a"]))),
+            ],
+            vec!["test"],
+        );
+        let diagnostics = cfg.validate();
+        assert!(diagnostics.iter().any(|d| d.message.contains("extends cycle")));
+    }
+
+    #[test]
+    fn shadowed_job_is_validated_only_by_its_effective_definition() {
+        // Parent's `build` extends a job that doesn't exist, but the child
+        // cleanly redefines `build` with no extends at all - only the
+        // effective (child) definition should ever be validated.
+        let parent = config(
+            vec![("build", job(Some("test"), Some(vec!["missing_job"])))],
+            vec!["test"],
+        );
+        let child = config_with_parent(vec![("build", job(Some("test"), None))], vec!["test"], Some(parent));
+
+        let diagnostics = child.validate();
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn clean_config_has_no_diagnostics() {
+        let cfg = config(vec![("test", job(Some("test"), None))], vec!["test"]);
+        assert!(cfg.validate().is_empty());
+    }
+}